@@ -0,0 +1,321 @@
+//! AST node extraction.
+//!
+//! Parses Rust source into a flat list of [`AstNode`]s, promoting each
+//! item's leading `///`/`//!` doc comment from invisible trivia into a
+//! first-class, queryable field.
+
+use syn::spanned::Spanned;
+use syn::{Attribute, Item};
+
+/// The kind of item an [`AstNode`] wraps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Fn,
+    Struct,
+    Enum,
+    Impl,
+    Trait,
+    Mod,
+    Const,
+    Static,
+    /// The crate root itself, synthesized from `syn::File::attrs` so a
+    /// leading `//!` crate doc comment has an [`AstNode`] to live on.
+    Crate,
+    Other,
+}
+
+impl ItemKind {
+    fn of(item: &Item) -> Self {
+        match item {
+            Item::Fn(_) => ItemKind::Fn,
+            Item::Struct(_) => ItemKind::Struct,
+            Item::Enum(_) => ItemKind::Enum,
+            Item::Impl(_) => ItemKind::Impl,
+            Item::Trait(_) => ItemKind::Trait,
+            Item::Mod(_) => ItemKind::Mod,
+            Item::Const(_) => ItemKind::Const,
+            Item::Static(_) => ItemKind::Static,
+            _ => ItemKind::Other,
+        }
+    }
+}
+
+/// 1-based start/end line and column of a node in the original source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl From<proc_macro2::Span> for Span {
+    fn from(span: proc_macro2::Span) -> Self {
+        let start = span.start();
+        let end = span.end();
+        Span {
+            start_line: start.line,
+            start_column: start.column + 1,
+            end_line: end.line,
+            end_column: end.column + 1,
+        }
+    }
+}
+
+impl Span {
+    /// The exact source text this span covers.
+    pub fn slice<'a>(&self, source: &'a str) -> &'a str {
+        &source[Self::offset_of(source, self.start_line, self.start_column)..Self::offset_of(source, self.end_line, self.end_column)]
+    }
+
+    /// Whether this span shares any source position with `other`.
+    pub fn overlaps(&self, other: &Span) -> bool {
+        (self.start_line, self.start_column) < (other.end_line, other.end_column)
+            && (other.start_line, other.start_column) < (self.end_line, self.end_column)
+    }
+
+    /// Convert a 1-based (line, column) position into a byte offset into
+    /// `source`. Columns are counted in chars, matching `proc_macro2`.
+    pub fn offset_of(source: &str, line: usize, column: usize) -> usize {
+        let mut offset = 0;
+        for (i, text) in source.split('\n').enumerate() {
+            if i + 1 == line {
+                let chars_offset: usize = text.chars().take(column - 1).map(char::len_utf8).sum();
+                return offset + chars_offset;
+            }
+            offset += text.len() + 1;
+        }
+        source.len()
+    }
+}
+
+/// One line of a doc comment, with the source span of the `#[doc = "..."]`
+/// attribute it came from (one `///` line desugars to one such attribute).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocLine {
+    pub text: String,
+    pub span: Span,
+    /// The column in the original source where `text` itself begins,
+    /// i.e. `span.start_column` plus the `///`/`//!` marker and the
+    /// single leading space stripped from the raw doc text (if any).
+    /// Lets callers map a position inside `text` back to real source.
+    pub text_column: usize,
+}
+
+/// The concatenated text of a contiguous run of leading `///` (or inner
+/// `//!`) lines, stripped of the comment marker and a single leading space.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocComment {
+    pub text: String,
+    /// Per-line text and source span, in source order. Used to locate
+    /// things like fenced doctest code blocks back in the original file.
+    pub lines: Vec<DocLine>,
+}
+
+impl DocComment {
+    /// Whether any doc text is present at all.
+    pub fn is_present(&self) -> bool {
+        !self.text.trim().is_empty()
+    }
+
+    /// Markdown headings (`# Heading`, `## Heading`, ...) found in the doc
+    /// text, with leading `#`s and surrounding whitespace stripped. Lines
+    /// inside fenced (```` ``` ````) code blocks are skipped, since a
+    /// hidden doctest setup line or example code starting with `#` is not
+    /// a heading.
+    pub fn sections(&self) -> Vec<String> {
+        let mut in_fence = false;
+        self.text
+            .lines()
+            .filter_map(|line| {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with("```") {
+                    in_fence = !in_fence;
+                    return None;
+                }
+                if in_fence {
+                    return None;
+                }
+                trimmed.strip_prefix('#')
+            })
+            .map(|rest| rest.trim_start_matches('#').trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// Whether a section with this exact heading text exists.
+    pub fn has_section(&self, name: &str) -> bool {
+        self.sections().iter().any(|s| s == name)
+    }
+}
+
+/// The length in columns of a `///` or `//!` doc comment marker.
+const DOC_MARKER_LEN: usize = 3;
+
+/// Extract the doc comment from an item's attributes. `///`/`//!` desugar
+/// to `#[doc = "..."]`, so this reads those out in source order.
+pub fn extract_doc(attrs: &[Attribute]) -> DocComment {
+    let mut lines = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("doc") {
+            continue;
+        }
+        if let syn::Meta::NameValue(nv) = &attr.meta {
+            if let syn::Expr::Lit(expr_lit) = &nv.value {
+                if let syn::Lit::Str(s) = &expr_lit.lit {
+                    let raw = s.value();
+                    let text = raw.strip_prefix(' ').unwrap_or(&raw).to_string();
+                    let span: Span = attr.span().into();
+                    let text_column = span.start_column + DOC_MARKER_LEN + (raw.len() - text.len());
+                    lines.push(DocLine { text, span, text_column });
+                }
+            }
+        }
+    }
+    DocComment {
+        text: lines.iter().map(|l| l.text.as_str()).collect::<Vec<_>>().join("\n"),
+        lines,
+    }
+}
+
+/// The underlying syntax node an [`AstNode`] was built from: an [`Item`]
+/// (top-level or inside an inline module), a method inside an `impl`
+/// block, or the crate root. Methods of `trait` blocks get no node.
+#[derive(Debug, Clone)]
+pub enum NodeItem {
+    Item(Box<Item>),
+    ImplItem(Box<syn::ImplItem>),
+    /// The crate root: not itself a [`syn::Item`], so there's no syntax
+    /// node to carry beyond its doc comment and span.
+    Crate,
+}
+
+/// One item in the AST, with its doc comment promoted to a queryable field.
+#[derive(Debug, Clone)]
+pub struct AstNode {
+    pub kind: ItemKind,
+    pub ident: Option<String>,
+    pub doc: DocComment,
+    pub span: Span,
+    pub is_pub: bool,
+    pub node: NodeItem,
+}
+
+/// Walk a parsed file and collect one [`AstNode`] per item, plus a
+/// synthesized crate-root node carrying any leading inner `//!` doc
+/// comment, recursing into `impl` blocks and inline modules so their
+/// methods and nested items are queryable too.
+pub fn collect_nodes(file: &syn::File) -> Vec<AstNode> {
+    let mut nodes = Vec::new();
+    nodes.push(AstNode {
+        kind: ItemKind::Crate,
+        ident: None,
+        doc: extract_doc(&file.attrs),
+        span: crate_span(&file.attrs),
+        is_pub: false,
+        node: NodeItem::Crate,
+    });
+    for item in &file.items {
+        collect_item(item, &mut nodes);
+    }
+    nodes
+}
+
+/// The span of the crate root: the extent of its inner-attribute list, or
+/// the very start of the file if it has none.
+fn crate_span(attrs: &[Attribute]) -> Span {
+    match (attrs.first(), attrs.last()) {
+        (Some(first), Some(last)) => {
+            let start: Span = first.span().into();
+            let end: Span = last.span().into();
+            Span {
+                start_line: start.start_line,
+                start_column: start.start_column,
+                end_line: end.end_line,
+                end_column: end.end_column,
+            }
+        }
+        _ => Span {
+            start_line: 1,
+            start_column: 1,
+            end_line: 1,
+            end_column: 1,
+        },
+    }
+}
+
+fn collect_item(item: &Item, nodes: &mut Vec<AstNode>) {
+    let (attrs, ident, is_pub): (&[Attribute], Option<String>, bool) = match item {
+        Item::Fn(f) => (
+            &f.attrs,
+            Some(f.sig.ident.to_string()),
+            matches!(f.vis, syn::Visibility::Public(_)),
+        ),
+        Item::Struct(s) => (
+            &s.attrs,
+            Some(s.ident.to_string()),
+            matches!(s.vis, syn::Visibility::Public(_)),
+        ),
+        Item::Enum(e) => (
+            &e.attrs,
+            Some(e.ident.to_string()),
+            matches!(e.vis, syn::Visibility::Public(_)),
+        ),
+        Item::Impl(i) => (&i.attrs, None, false),
+        Item::Trait(t) => (
+            &t.attrs,
+            Some(t.ident.to_string()),
+            matches!(t.vis, syn::Visibility::Public(_)),
+        ),
+        Item::Mod(m) => (
+            &m.attrs,
+            Some(m.ident.to_string()),
+            matches!(m.vis, syn::Visibility::Public(_)),
+        ),
+        Item::Const(c) => (
+            &c.attrs,
+            Some(c.ident.to_string()),
+            matches!(c.vis, syn::Visibility::Public(_)),
+        ),
+        Item::Static(s) => (
+            &s.attrs,
+            Some(s.ident.to_string()),
+            matches!(s.vis, syn::Visibility::Public(_)),
+        ),
+        _ => (&[], None, false),
+    };
+
+    nodes.push(AstNode {
+        kind: ItemKind::of(item),
+        ident,
+        doc: extract_doc(attrs),
+        span: item.span().into(),
+        is_pub,
+        node: NodeItem::Item(Box::new(item.clone())),
+    });
+
+    match item {
+        Item::Impl(imp) => {
+            for impl_item in &imp.items {
+                if let syn::ImplItem::Fn(f) = impl_item {
+                    nodes.push(AstNode {
+                        kind: ItemKind::Fn,
+                        ident: Some(f.sig.ident.to_string()),
+                        doc: extract_doc(&f.attrs),
+                        span: f.span().into(),
+                        is_pub: matches!(f.vis, syn::Visibility::Public(_)),
+                        node: NodeItem::ImplItem(Box::new(impl_item.clone())),
+                    });
+                }
+            }
+        }
+        Item::Mod(m) => {
+            if let Some((_, items)) = &m.content {
+                for inner in items {
+                    collect_item(inner, nodes);
+                }
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,184 @@
+//! Extraction and recursive matching of rustdoc doctests: the fenced code
+//! blocks inside a doc comment that `rustdoc --test` compiles and runs.
+//!
+//! Each block is re-parsed as its own nested [`AstNode`] tree so the same
+//! [`Pattern`] language can search the items inside it by kind, visibility
+//! and doc text. Expressions and statements inside a doctest are not
+//! matched, and a block that does not parse as a whole Rust file (e.g.
+//! bare `let` statements) yields no nodes.
+
+use crate::ast::{self, AstNode, DocComment, Span};
+use crate::pattern::Pattern;
+
+/// Modifiers parsed from a fence info string, e.g. ```` ```rust,no_run ````.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DoctestAttrs {
+    pub no_run: bool,
+    pub ignore: bool,
+    pub should_panic: bool,
+    pub compile_fail: bool,
+}
+
+/// One fenced code block extracted from a doc comment.
+#[derive(Debug, Clone)]
+pub struct Doctest {
+    pub attrs: DoctestAttrs,
+    /// The de-hidden Rust source rustdoc would actually compile: `# `-prefixed
+    /// hidden lines have the marker stripped but are kept.
+    pub code: String,
+    /// Source span of the fence's first code line, used to map matches
+    /// inside the doctest back to the original file.
+    pub origin: Span,
+    /// For each line of `code` (same indexing), the column in the
+    /// original source where that line's kept text begins - i.e. past
+    /// the `///`/`//!` marker, its stripped leading space, and any
+    /// hidden-line marker [`dehide`] stripped. Used to map a match's
+    /// column inside `code` back to the original file.
+    line_columns: Vec<usize>,
+}
+
+impl Doctest {
+    /// Parse this doctest's code as its own AST, or `None` if it's
+    /// `ignore`d (rustdoc never compiles it, so there's nothing to search)
+    /// or doesn't parse as a standalone Rust file (e.g. it's a bare
+    /// expression, or genuinely broken).
+    pub fn nodes(&self) -> Option<Vec<AstNode>> {
+        if self.attrs.ignore {
+            return None;
+        }
+        syn::parse_file(&self.code).ok().map(|file| ast::collect_nodes(&file))
+    }
+
+    /// Whether any node in this doctest's AST matches `pattern`.
+    pub fn matches(&self, pattern: &Pattern) -> bool {
+        self.nodes()
+            .is_some_and(|nodes| nodes.iter().any(|node| pattern.matches(node)))
+    }
+
+    /// Map a (1-based line, 1-based column) position inside `code` back
+    /// to its column in the original source. Falls back to the
+    /// unmapped column if `line` is out of range, which shouldn't happen
+    /// for a span produced by parsing `code` itself.
+    fn column_of(&self, line: usize, column: usize) -> usize {
+        match self.line_columns.get(line - 1) {
+            Some(&origin_column) => origin_column + column - 1,
+            None => column,
+        }
+    }
+}
+
+/// Extract every fenced code block from `doc` that rustdoc would treat as
+/// a (possibly modified) Rust doctest, skipping fences tagged with another
+/// language.
+pub fn extract_doctests(doc: &DocComment) -> Vec<Doctest> {
+    let mut doctests = Vec::new();
+    let mut lines = doc.lines.iter();
+
+    while let Some(line) = lines.next() {
+        let Some(info) = line.text.trim_start().strip_prefix("```") else {
+            continue;
+        };
+        let Some(attrs) = parse_fence_info(info) else {
+            // Not a Rust fence: skip its contents without recursing, but
+            // still consume through the closing ``` so it isn't mistaken
+            // for the start of the next fence.
+            for inner in lines.by_ref() {
+                if inner.text.trim_start().starts_with("```") {
+                    break;
+                }
+            }
+            continue;
+        };
+
+        let mut code_lines = Vec::new();
+        let mut line_columns = Vec::new();
+        let mut origin = None;
+        for inner in lines.by_ref() {
+            if inner.text.trim_start().starts_with("```") {
+                break;
+            }
+            if origin.is_none() {
+                origin = Some(inner.span);
+            }
+            let (code, hidden_len) = dehide(&inner.text);
+            line_columns.push(inner.text_column + hidden_len);
+            code_lines.push(code);
+        }
+
+        doctests.push(Doctest {
+            attrs,
+            code: code_lines.join("\n"),
+            origin: origin.unwrap_or(line.span),
+            line_columns,
+        });
+    }
+    doctests
+}
+
+/// Strip a hidden-line marker (`# ` or a bare `#`) from a doctest source
+/// line, the way rustdoc does before compiling it. Returns the stripped
+/// text and how many columns of marker were removed from its front, so
+/// callers can keep mapping columns back to the original source.
+fn dehide(line: &str) -> (String, usize) {
+    if let Some(rest) = line.strip_prefix("# ") {
+        (rest.to_string(), 2)
+    } else if line == "#" {
+        (String::new(), 1)
+    } else {
+        (line.to_string(), 0)
+    }
+}
+
+/// Parse a fence info string (the text right after the opening ```` ``` ````)
+/// into its doctest modifiers, or `None` if it names a non-Rust language.
+fn parse_fence_info(info: &str) -> Option<DoctestAttrs> {
+    let mut attrs = DoctestAttrs::default();
+    for (index, token) in info.trim().split(',').map(str::trim).enumerate() {
+        if token.is_empty() {
+            continue;
+        }
+        match token {
+            "rust" => {}
+            "no_run" => attrs.no_run = true,
+            "ignore" => attrs.ignore = true,
+            "should_panic" => attrs.should_panic = true,
+            "compile_fail" => attrs.compile_fail = true,
+            t if t.starts_with("edition") => {}
+            _ if index == 0 => return None,
+            _ => {}
+        }
+    }
+    Some(attrs)
+}
+
+/// A match found by recursing `pattern` into a doctest's nested AST, with
+/// its location mapped back to line/column in the original file.
+#[derive(Debug, Clone)]
+pub struct DoctestMatch {
+    pub node: AstNode,
+    pub location: Span,
+}
+
+/// Search every doctest in `node`'s doc comment for matches of `pattern`,
+/// mapping each match's span back to the original source.
+pub fn search_doctests(node: &AstNode, pattern: &Pattern) -> Vec<DoctestMatch> {
+    let mut matches = Vec::new();
+    for doctest in extract_doctests(&node.doc) {
+        let Some(nodes) = doctest.nodes() else {
+            continue;
+        };
+        for nested in nodes {
+            if !pattern.matches(&nested) {
+                continue;
+            }
+            let location = Span {
+                start_line: doctest.origin.start_line + nested.span.start_line.saturating_sub(1),
+                start_column: doctest.column_of(nested.span.start_line, nested.span.start_column),
+                end_line: doctest.origin.start_line + nested.span.end_line.saturating_sub(1),
+                end_column: doctest.column_of(nested.span.end_line, nested.span.end_column),
+            };
+            matches.push(DoctestMatch { node: nested, location });
+        }
+    }
+    matches
+}
@@ -0,0 +1,22 @@
+//! Structural search, rewrite, and reporting over Rust ASTs.
+//!
+//! [`ast`] turns parsed source into queryable nodes with doc comments
+//! promoted to first-class fields; [`pattern`] matches those nodes against
+//! a small query language (recursing into [`doctest`]s); [`rewrite`] turns
+//! matches into text edits; [`report`] renders matches as JSON or HTML.
+
+pub mod ast;
+pub mod doctest;
+pub mod pattern;
+pub mod report;
+pub mod rewrite;
+
+use ast::AstNode;
+use pattern::Pattern;
+
+/// Parse `source` and return every [`AstNode`] matching `pattern`.
+pub fn search(source: &str, pattern: &Pattern) -> syn::Result<Vec<AstNode>> {
+    let file = syn::parse_file(source)?;
+    let nodes = ast::collect_nodes(&file);
+    Ok(nodes.into_iter().filter(|node| pattern.matches(node)).collect())
+}
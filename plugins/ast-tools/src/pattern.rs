@@ -0,0 +1,190 @@
+//! A small query language for matching [`AstNode`]s by kind, visibility,
+//! and documentation.
+//!
+//! A pattern is a space-separated list of clauses, all of which must hold:
+//!
+//! - `kind:fn` / `kind:struct` / `kind:impl` / `kind:trait` / `kind:mod` /
+//!   `kind:enum` / `kind:const` / `kind:static` / `kind:crate` — restrict
+//!   to an item kind (`crate` matches the synthesized crate-root node
+//!   carrying the file's leading `//!` doc comment, if any)
+//! - `pub:true` / `pub:false` — restrict to public/non-public items
+//! - `doc:"substring"` — doc text must contain the substring
+//! - `doc:/regex/` — doc text must match the regex
+//! - `has_doc` / `no_doc` — doc text is present / absent
+//! - `has_section("Examples")` — doc contains a Markdown heading with this
+//!   exact text
+//! - `doctest(<pattern>)` — at least one of the node's doctests, re-parsed
+//!   as its own AST, has a node matching the nested pattern
+//!
+//! # Examples
+//!
+//! ```
+//! use ast_tools::pattern::Pattern;
+//!
+//! // Find undocumented public items, e.g. to lint API coverage.
+//! let pattern = Pattern::parse("pub:true no_doc").unwrap();
+//! ```
+
+use crate::ast::{AstNode, ItemKind};
+use regex::Regex;
+use std::fmt;
+
+/// A single constraint a node must satisfy.
+#[derive(Debug, Clone)]
+enum Clause {
+    Kind(ItemKind),
+    Pub(bool),
+    DocContains(String),
+    DocRegex(Regex),
+    HasDoc,
+    NoDoc,
+    HasSection(String),
+    Doctest(Box<Pattern>),
+}
+
+/// A compiled pattern: a conjunction of [`Clause`]s.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    clauses: Vec<Clause>,
+}
+
+/// An error produced while parsing a pattern string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError(pub String);
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid pattern: {}", self.0)
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl Pattern {
+    /// Parse a pattern string into a matcher. See the [module docs](self)
+    /// for the clause syntax.
+    pub fn parse(query: &str) -> Result<Self, PatternError> {
+        let clauses = tokenize(query)?
+            .iter()
+            .map(|token| parse_clause(token))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Pattern { clauses })
+    }
+
+    /// Whether `node` satisfies every clause in this pattern.
+    pub fn matches(&self, node: &AstNode) -> bool {
+        self.clauses.iter().all(|clause| clause_matches(clause, node))
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<String>, PatternError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut paren_depth = 0i32;
+
+    for c in query.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            '(' if !in_quotes => {
+                paren_depth += 1;
+                current.push(c);
+            }
+            ')' if !in_quotes => {
+                paren_depth -= 1;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes && paren_depth == 0 => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(PatternError("unterminated string literal".into()));
+    }
+    if paren_depth != 0 {
+        return Err(PatternError("unbalanced parentheses".into()));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn unquote(s: &str) -> Result<String, PatternError> {
+    let s = s.trim();
+    if s.len() >= 2 && s.starts_with('"') && s.ends_with('"') {
+        Ok(s[1..s.len() - 1].to_string())
+    } else {
+        Err(PatternError(format!("expected a quoted string, got `{s}`")))
+    }
+}
+
+fn parse_kind(s: &str) -> Result<ItemKind, PatternError> {
+    match s {
+        "fn" => Ok(ItemKind::Fn),
+        "struct" => Ok(ItemKind::Struct),
+        "enum" => Ok(ItemKind::Enum),
+        "impl" => Ok(ItemKind::Impl),
+        "trait" => Ok(ItemKind::Trait),
+        "mod" => Ok(ItemKind::Mod),
+        "const" => Ok(ItemKind::Const),
+        "static" => Ok(ItemKind::Static),
+        "crate" => Ok(ItemKind::Crate),
+        other => Err(PatternError(format!("unknown kind `{other}`"))),
+    }
+}
+
+fn parse_clause(token: &str) -> Result<Clause, PatternError> {
+    if token == "has_doc" {
+        return Ok(Clause::HasDoc);
+    }
+    if token == "no_doc" {
+        return Ok(Clause::NoDoc);
+    }
+    if let Some(inner) = token
+        .strip_prefix("has_section(")
+        .and_then(|s| s.strip_suffix(')'))
+    {
+        return Ok(Clause::HasSection(unquote(inner)?));
+    }
+    if let Some(inner) = token.strip_prefix("doctest(").and_then(|s| s.strip_suffix(')')) {
+        return Ok(Clause::Doctest(Box::new(Pattern::parse(inner)?)));
+    }
+    if let Some(rest) = token.strip_prefix("kind:") {
+        return Ok(Clause::Kind(parse_kind(rest)?));
+    }
+    if let Some(rest) = token.strip_prefix("pub:") {
+        return Ok(Clause::Pub(rest == "true"));
+    }
+    if let Some(rest) = token.strip_prefix("doc:") {
+        if rest.len() >= 2 && rest.starts_with('/') && rest.ends_with('/') {
+            let re = Regex::new(&rest[1..rest.len() - 1])
+                .map_err(|e| PatternError(format!("bad regex: {e}")))?;
+            return Ok(Clause::DocRegex(re));
+        }
+        return Ok(Clause::DocContains(unquote(rest)?));
+    }
+    Err(PatternError(format!("unrecognized clause `{token}`")))
+}
+
+fn clause_matches(clause: &Clause, node: &AstNode) -> bool {
+    match clause {
+        Clause::Kind(kind) => node.kind == *kind,
+        Clause::Pub(want) => node.is_pub == *want,
+        Clause::DocContains(needle) => node.doc.text.contains(needle.as_str()),
+        Clause::DocRegex(re) => re.is_match(&node.doc.text),
+        Clause::HasDoc => node.doc.is_present(),
+        Clause::NoDoc => !node.doc.is_present(),
+        Clause::HasSection(name) => node.doc.has_section(name),
+        Clause::Doctest(nested) => crate::doctest::extract_doctests(&node.doc)
+            .iter()
+            .any(|doctest| doctest.matches(nested)),
+    }
+}
@@ -0,0 +1,179 @@
+//! Structured, machine-readable output for search (and rewrite) matches:
+//! a stable JSON-lines schema for piping into other tools, and a small
+//! rustdoc-style HTML index for browsing.
+
+use crate::ast::AstNode;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// The byte/line/column span of a [`ReportMatch`], in both forms so
+/// consumers can pick whichever addressing they need.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SpanRecord {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+impl SpanRecord {
+    fn new(source: &str, span: crate::ast::Span) -> Self {
+        SpanRecord {
+            start_line: span.start_line,
+            start_column: span.start_column,
+            end_line: span.end_line,
+            end_column: span.end_column,
+            start_byte: crate::ast::Span::offset_of(source, span.start_line, span.start_column),
+            end_byte: crate::ast::Span::offset_of(source, span.end_line, span.end_column),
+        }
+    }
+}
+
+/// One match, ready to serialize: which file it's in, its span, its item
+/// kind, any captured metavariables (empty for a plain search match), and
+/// its surrounding doc text.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ReportMatch {
+    pub file: String,
+    pub kind: String,
+    pub ident: Option<String>,
+    pub span: SpanRecord,
+    pub doc: String,
+    pub snippet: String,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub captures: HashMap<String, String>,
+}
+
+impl ReportMatch {
+    /// Build a report entry for a plain [`AstNode`] search match.
+    pub fn from_node(file: &str, source: &str, node: &AstNode) -> Self {
+        Self::from_node_with_captures(file, source, node, HashMap::new())
+    }
+
+    /// Build a report entry for a node matched with rewrite-style
+    /// metavariable captures attached.
+    pub fn from_node_with_captures(
+        file: &str,
+        source: &str,
+        node: &AstNode,
+        captures: HashMap<String, String>,
+    ) -> Self {
+        ReportMatch {
+            file: file.to_string(),
+            kind: format!("{:?}", node.kind).to_lowercase(),
+            ident: node.ident.clone(),
+            span: SpanRecord::new(source, node.span),
+            doc: node.doc.text.clone(),
+            snippet: node.span.slice(source).to_string(),
+            captures,
+        }
+    }
+}
+
+/// Write one JSON object per match, newline-delimited, so large trees can
+/// be streamed into other tools without buffering the whole report.
+pub fn write_jsonl<W: Write>(matches: &[ReportMatch], mut writer: W) -> io::Result<()> {
+    for found in matches {
+        serde_json::to_writer(&mut writer, found)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Render matches as a single rustdoc-style HTML index page: an item list
+/// up top linking down to a section per match, with its doc summary and
+/// matched snippet (metavariable captures highlighted, when present).
+pub fn render_html(matches: &[ReportMatch]) -> String {
+    let mut nav = String::new();
+    let mut sections = String::new();
+
+    for (i, found) in matches.iter().enumerate() {
+        let anchor = format!("match-{i}");
+        let label = found.ident.as_deref().unwrap_or("<anonymous>");
+
+        nav.push_str(&format!(
+            "<li><a href=\"#{anchor}\"><code>{}</code> <span class=\"kind\">{}</span></a></li>\n",
+            escape(label),
+            escape(&found.kind)
+        ));
+
+        sections.push_str(&format!(
+            "<section id=\"{anchor}\">\n\
+             <h2><code>{}</code> <span class=\"kind\">{}</span></h2>\n\
+             <p class=\"location\">{} {}:{}</p>\n",
+            escape(label),
+            escape(&found.kind),
+            escape(&found.file),
+            found.span.start_line,
+            found.span.start_column,
+        ));
+        if !found.doc.is_empty() {
+            sections.push_str(&format!("<p class=\"doc\">{}</p>\n", escape(&found.doc)));
+        }
+        sections.push_str(&format!("<pre><code>{}</code></pre>\n</section>\n", highlight(&found.snippet, &found.captures)));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n\
+         <html lang=\"en\">\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>ast-tools report</title>\n\
+         <style>\n\
+         body {{ font-family: sans-serif; margin: 2rem; }}\n\
+         .kind {{ color: #888; font-size: 0.85em; }}\n\
+         .doc {{ color: #444; }}\n\
+         .location {{ color: #888; font-size: 0.85em; }}\n\
+         pre {{ background: #f6f8fa; padding: 0.75rem; overflow-x: auto; }}\n\
+         mark.meta {{ background: #fff3a3; }}\n\
+         </style>\n\
+         </head>\n\
+         <body>\n\
+         <h1>Matches</h1>\n\
+         <nav><ul>\n{nav}</ul></nav>\n\
+         {sections}\
+         </body>\n\
+         </html>\n"
+    )
+}
+
+/// Escape a matched snippet for HTML and wrap each metavariable capture's
+/// text in a `<mark>` so it stands out, best-effort (a capture that
+/// appears more than once in the snippet only highlights its first
+/// occurrence).
+fn highlight(snippet: &str, captures: &HashMap<String, String>) -> String {
+    let mut spans: Vec<(usize, usize, &str)> = Vec::new();
+    for (name, text) in captures {
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(start) = snippet.find(text.as_str()) {
+            spans.push((start, start + text.len(), name));
+        }
+    }
+    spans.sort_by_key(|(start, ..)| *start);
+
+    let mut out = String::new();
+    let mut cursor = 0;
+    for (start, end, name) in spans {
+        if start < cursor {
+            continue;
+        }
+        out.push_str(&escape(&snippet[cursor..start]));
+        out.push_str(&format!("<mark class=\"meta\" title=\"{}\">", escape(name)));
+        out.push_str(&escape(&snippet[start..end]));
+        out.push_str("</mark>");
+        cursor = end;
+    }
+    out.push_str(&escape(&snippet[cursor..]));
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,359 @@
+//! Structural search-and-replace with metavariable substitution.
+//!
+//! A [`Rewrite`] pairs a token-level search pattern containing named
+//! metavariables (`$NAME`) with a replacement template referencing the
+//! same names, and turns each match into a minimal text [`Edit`] rather
+//! than a reprinted AST - so formatting and comments outside the match
+//! are left untouched.
+//!
+//! Matching works at whole-item granularity: a pattern is compared against
+//! an item's tokens with all of its attributes stripped, doc comments
+//! included (doc text is matched separately via [`crate::pattern`]), so `fn $NAME($ARGS) -> $RET
+//! { $BODY }` matches a function's visibility, signature and body exactly
+//! as written, binding the named holes to the corresponding source text.
+//!
+//! # Examples
+//!
+//! ```
+//! use ast_tools::rewrite::Rewrite;
+//!
+//! let rewrite = Rewrite::new(
+//!     "fn legacy_greet($ARGS) -> $RET { $BODY }",
+//!     "fn greet($ARGS) -> $RET { $BODY }",
+//! )
+//! .unwrap();
+//! let (rewritten, edits) = rewrite.apply("fn legacy_greet(name: &str) -> String { name.into() }").unwrap();
+//! assert_eq!(edits.len(), 1);
+//! assert!(rewritten.contains("fn greet"));
+//! ```
+
+use crate::ast::{self, AstNode, NodeItem, Span};
+use proc_macro2::TokenTree;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use syn::spanned::Spanned;
+use syn::Item;
+
+/// An error raised while parsing or applying a [`Rewrite`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RewriteError(pub String);
+
+impl fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rewrite error: {}", self.0)
+    }
+}
+
+impl std::error::Error for RewriteError {}
+
+/// A single match of a rewrite's pattern: the span it would replace, and
+/// the verbatim source text captured for each metavariable.
+#[derive(Debug, Clone)]
+pub struct Match {
+    pub span: Span,
+    pub captures: HashMap<String, String>,
+}
+
+/// A minimal text edit: replace `[span]` in the original source with
+/// `replacement`.
+#[derive(Debug, Clone)]
+pub struct Edit {
+    pub span: Span,
+    pub replacement: String,
+}
+
+/// A parsed search-and-replace rule.
+pub struct Rewrite {
+    pattern: Vec<TokenTree>,
+    template: String,
+}
+
+impl Rewrite {
+    /// Parse a token pattern (with `$NAME`-style metavariables) and a
+    /// replacement template referencing the same names.
+    pub fn new(pattern: &str, template: &str) -> Result<Self, RewriteError> {
+        let stream = proc_macro2::TokenStream::from_str(pattern)
+            .map_err(|e| RewriteError(format!("bad pattern: {e}")))?;
+        Ok(Rewrite {
+            pattern: stream.into_iter().collect(),
+            template: template.to_string(),
+        })
+    }
+
+    /// Find every match of this rewrite's pattern in `source`, without
+    /// changing anything.
+    pub fn find_matches(&self, source: &str) -> syn::Result<Vec<Match>> {
+        let file = syn::parse_file(source)?;
+        let nodes = ast::collect_nodes(&file);
+        let mut matches = Vec::new();
+        for node in &nodes {
+            let tokens = item_tokens(node);
+            let mut captures = HashMap::new();
+            if match_seq(&self.pattern, &tokens, &mut captures) {
+                let captures = captures
+                    .into_iter()
+                    .map(|(name, tokens)| (name, capture_text(&tokens, source)))
+                    .collect();
+                matches.push(Match { span: match_span(node), captures });
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Render the replacement text for a single [`Match`] by substituting
+    /// its captures into the template.
+    pub fn render(&self, found: &Match) -> String {
+        let mut out = String::new();
+        let mut rest = self.template.as_str();
+        while let Some(dollar) = rest.find('$') {
+            out.push_str(&rest[..dollar]);
+            rest = &rest[dollar + 1..];
+            let name_len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            let (name, remainder) = rest.split_at(name_len);
+            rest = remainder;
+            match found.captures.get(name) {
+                Some(text) => out.push_str(text),
+                None => {
+                    out.push('$');
+                    out.push_str(name);
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Find every match, render each replacement, and apply them all as
+    /// one set of non-overlapping edits over `source`.
+    ///
+    /// Refuses (returns an error, changing nothing) if any two matches
+    /// overlap, or if a rendered replacement would itself re-match this
+    /// rewrite's pattern - which would make the rewrite non-idempotent.
+    pub fn apply(&self, source: &str) -> Result<(String, Vec<Edit>), RewriteError> {
+        let matches = self
+            .find_matches(source)
+            .map_err(|e| RewriteError(format!("failed to parse source: {e}")))?;
+
+        for (i, a) in matches.iter().enumerate() {
+            for b in &matches[i + 1..] {
+                if a.span.overlaps(&b.span) {
+                    return Err(RewriteError(format!(
+                        "overlapping matches at {:?} and {:?}; refusing to rewrite",
+                        a.span, b.span
+                    )));
+                }
+            }
+        }
+
+        let mut edits = Vec::with_capacity(matches.len());
+        for found in &matches {
+            let replacement = self.render(found);
+            if self.rematches(&replacement) {
+                return Err(RewriteError(format!(
+                    "replacement for match at {:?} would re-match the search pattern; refusing to apply (not idempotent)",
+                    found.span
+                )));
+            }
+            edits.push(Edit { span: found.span, replacement });
+        }
+
+        Ok((apply_edits(source, &edits), edits))
+    }
+
+    /// Whether `text`, parsed as a standalone item, matches this rewrite's
+    /// own pattern (used by the idempotency guard in [`Rewrite::apply`]).
+    fn rematches(&self, text: &str) -> bool {
+        self.find_matches(text).is_ok_and(|m| !m.is_empty())
+    }
+}
+
+/// Apply non-overlapping edits to `source` in one front-to-back pass, with
+/// every edit's offsets taken from the original text so earlier
+/// replacements cannot shift later spans.
+fn apply_edits(source: &str, edits: &[Edit]) -> String {
+    let mut sorted: Vec<&Edit> = edits.iter().collect();
+    sorted.sort_by_key(|e| (e.span.start_line, e.span.start_column));
+    let mut out = String::new();
+    let mut cursor = 0usize;
+    let offsets: Vec<usize> = sorted
+        .iter()
+        .flat_map(|e| {
+            [
+                Span::offset_of(source, e.span.start_line, e.span.start_column),
+                Span::offset_of(source, e.span.end_line, e.span.end_column),
+            ]
+        })
+        .collect();
+    for (edit, pair) in sorted.iter().zip(offsets.chunks(2)) {
+        let (start, end) = (pair[0], pair[1]);
+        out.push_str(&source[cursor..start]);
+        out.push_str(&edit.replacement);
+        cursor = end;
+    }
+    out.push_str(&source[cursor..]);
+    out
+}
+
+/// A unified-diff-style rendering of one edit, for dry-run review.
+pub fn diff(source: &str, edit: &Edit) -> String {
+    let old = edit.span.slice(source);
+    let mut out = String::new();
+    for line in old.lines() {
+        out.push_str("- ");
+        out.push_str(line);
+        out.push('\n');
+    }
+    for line in edit.replacement.lines() {
+        out.push_str("+ ");
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// The span of a match's edit: the item's visibility/signature/body, but
+/// *not* its leading doc comment and attributes, so a rewrite only ever
+/// replaces the shape it matched against and leaves docs/derives in place.
+fn match_span(node: &AstNode) -> Span {
+    match &node.node {
+        NodeItem::Item(item) => {
+            let mut item = (**item).clone();
+            clear_attrs(&mut item);
+            item.span().into()
+        }
+        NodeItem::ImplItem(impl_item) => {
+            let mut impl_item = (**impl_item).clone();
+            if let syn::ImplItem::Fn(f) = &mut impl_item {
+                f.attrs.clear();
+            }
+            impl_item.span().into()
+        }
+        NodeItem::Crate => node.span,
+    }
+}
+
+/// The token stream for an item's visibility/signature/body, with its doc
+/// comment (and any other) attributes stripped so only shape is matched.
+fn item_tokens(node: &AstNode) -> Vec<TokenTree> {
+    match &node.node {
+        NodeItem::Item(item) => {
+            let mut item = (**item).clone();
+            clear_attrs(&mut item);
+            quote::quote!(#item).into_iter().collect()
+        }
+        NodeItem::ImplItem(impl_item) => {
+            let mut impl_item = (**impl_item).clone();
+            if let syn::ImplItem::Fn(f) = &mut impl_item {
+                f.attrs.clear();
+            }
+            quote::quote!(#impl_item).into_iter().collect()
+        }
+        // The crate root has no tokens of its own to match against - only
+        // its doc comment, which `pattern` matches separately.
+        NodeItem::Crate => Vec::new(),
+    }
+}
+
+fn clear_attrs(item: &mut Item) {
+    match item {
+        Item::Fn(f) => f.attrs.clear(),
+        Item::Struct(s) => s.attrs.clear(),
+        Item::Enum(e) => e.attrs.clear(),
+        Item::Impl(i) => i.attrs.clear(),
+        Item::Trait(t) => t.attrs.clear(),
+        Item::Mod(m) => m.attrs.clear(),
+        Item::Const(c) => c.attrs.clear(),
+        Item::Static(s) => s.attrs.clear(),
+        _ => {}
+    }
+}
+
+/// The verbatim source text spanning a capture's first to last token.
+fn capture_text(tokens: &[TokenTree], source: &str) -> String {
+    let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+        return String::new();
+    };
+    let start: Span = first.span().into();
+    let end: Span = last.span().into();
+    let span = Span {
+        start_line: start.start_line,
+        start_column: start.start_column,
+        end_line: end.end_line,
+        end_column: end.end_column,
+    };
+    span.slice(source).to_string()
+}
+
+/// Match `pattern` against `input` token-for-token, treating a `$NAME`
+/// pair in `pattern` as a metavariable that captures zero or more of
+/// `input`'s tokens, trying the shortest capture first and backtracking to
+/// longer ones, recursing into groups so a
+/// metavariable can also stand for an entire parenthesized/braced region.
+fn match_seq(pattern: &[TokenTree], input: &[TokenTree], captures: &mut HashMap<String, Vec<TokenTree>>) -> bool {
+    if let [TokenTree::Punct(p), TokenTree::Ident(name), rest_pattern @ ..] = pattern {
+        if p.as_char() == '$' {
+            let name = name.to_string();
+            for split in 0..=input.len() {
+                let (candidate, remaining) = input.split_at(split);
+                let mut trial = captures.clone();
+                if !bind(&mut trial, &name, candidate) {
+                    continue;
+                }
+                if match_seq(rest_pattern, remaining, &mut trial) {
+                    *captures = trial;
+                    return true;
+                }
+            }
+            return false;
+        }
+    }
+
+    match (pattern.first(), input.first()) {
+        (None, None) => true,
+        (None, Some(_)) | (Some(_), None) => false,
+        (Some(TokenTree::Group(pg)), Some(TokenTree::Group(ig))) => {
+            if pg.delimiter() != ig.delimiter() {
+                return false;
+            }
+            let pg_tokens: Vec<TokenTree> = pg.stream().into_iter().collect();
+            let ig_tokens: Vec<TokenTree> = ig.stream().into_iter().collect();
+            let mut trial = captures.clone();
+            if !match_seq(&pg_tokens, &ig_tokens, &mut trial) {
+                return false;
+            }
+            *captures = trial;
+            match_seq(&pattern[1..], &input[1..], captures)
+        }
+        (Some(pat), Some(tok)) if tokens_eq(pat, tok) => match_seq(&pattern[1..], &input[1..], captures),
+        _ => false,
+    }
+}
+
+/// Bind a metavariable to a candidate token slice, requiring a repeated
+/// occurrence of the same name to capture identical text.
+fn bind(captures: &mut HashMap<String, Vec<TokenTree>>, name: &str, candidate: &[TokenTree]) -> bool {
+    match captures.get(name) {
+        Some(existing) => token_seq_eq(existing, candidate),
+        None => {
+            captures.insert(name.to_string(), candidate.to_vec());
+            true
+        }
+    }
+}
+
+fn token_seq_eq(a: &[TokenTree], b: &[TokenTree]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| tokens_eq(x, y))
+}
+
+fn tokens_eq(a: &TokenTree, b: &TokenTree) -> bool {
+    match (a, b) {
+        (TokenTree::Ident(x), TokenTree::Ident(y)) => x == y,
+        (TokenTree::Punct(x), TokenTree::Punct(y)) => x.as_char() == y.as_char(),
+        (TokenTree::Literal(x), TokenTree::Literal(y)) => x.to_string() == y.to_string(),
+        (TokenTree::Group(x), TokenTree::Group(y)) => {
+            x.delimiter() == y.delimiter() && x.stream().to_string() == y.stream().to_string()
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,128 @@
+use ast_tools::doctest::search_doctests;
+use ast_tools::pattern::Pattern;
+use ast_tools::search;
+
+const GREETER_DOC: &str = r#"
+/// A struct that greets people.
+///
+/// ```
+/// let g = Greeter::new("Hello");
+/// ```
+pub struct Greeter;
+"#;
+
+#[test]
+fn finds_doctest_missing_an_import() {
+    let source = r#"
+/// Builds a greeting.
+///
+/// ```
+/// let message = build("World");
+/// ```
+pub fn build(name: &str) -> String {
+    format!("Hello, {name}!")
+}
+"#;
+    let nodes = search(source, &Pattern::parse("kind:fn").unwrap()).unwrap();
+    let build_fn = nodes.iter().find(|n| n.ident.as_deref() == Some("build")).unwrap();
+
+    let doctests = ast_tools::doctest::extract_doctests(&build_fn.doc);
+    assert_eq!(doctests.len(), 1);
+    assert!(doctests[0].code.contains("let message = build"));
+    assert!(!doctests[0].code.contains("use "));
+}
+
+#[test]
+fn statement_only_doctest_yields_no_item_matches() {
+    let nodes = search(GREETER_DOC, &Pattern::parse("kind:struct").unwrap()).unwrap();
+    let greeter = &nodes[0];
+
+    let matches = search_doctests(greeter, &Pattern::parse("kind:fn").unwrap());
+    // The snippet is a single `let` statement, not a function item, so a
+    // `kind:fn` pattern finds nothing, but it must not panic or misparse.
+    assert!(matches.is_empty());
+}
+
+#[test]
+fn maps_doctest_match_column_past_the_comment_marker() {
+    let source = "/// ```\n/// pub fn helper() {}\n/// ```\npub fn with_doctest() {}\n";
+    let nodes = search(source, &Pattern::parse("kind:fn").unwrap()).unwrap();
+    let with_doctest = nodes.iter().find(|n| n.ident.as_deref() == Some("with_doctest")).unwrap();
+
+    let matches = search_doctests(with_doctest, &Pattern::parse("kind:fn").unwrap());
+    assert_eq!(matches.len(), 1);
+    // `pub fn helper() {}` is on source line 2, starting right after the
+    // `/// ` marker - column 5, not column 1 of the re-parsed snippet.
+    assert_eq!(matches[0].location.start_line, 2);
+    assert_eq!(matches[0].location.start_column, 5);
+}
+
+#[test]
+fn skips_non_rust_fences() {
+    let source = r#"
+/// Some notes.
+///
+/// ```text
+/// this is not rust
+/// ```
+pub fn noop() {}
+"#;
+    let nodes = search(source, &Pattern::parse("kind:fn").unwrap()).unwrap();
+    let noop = nodes.iter().find(|n| n.ident.as_deref() == Some("noop")).unwrap();
+    assert!(ast_tools::doctest::extract_doctests(&noop.doc).is_empty());
+}
+
+#[test]
+fn recognizes_fence_modifiers() {
+    let source = r#"
+/// ```no_run,should_panic
+/// panic!("boom");
+/// ```
+pub fn boom() {}
+"#;
+    let nodes = search(source, &Pattern::parse("kind:fn").unwrap()).unwrap();
+    let boom = nodes.iter().find(|n| n.ident.as_deref() == Some("boom")).unwrap();
+    let doctests = ast_tools::doctest::extract_doctests(&boom.doc);
+    assert_eq!(doctests.len(), 1);
+    assert!(doctests[0].attrs.no_run);
+    assert!(doctests[0].attrs.should_panic);
+}
+
+#[test]
+fn ignored_doctests_are_never_searched() {
+    let source = r#"
+/// ```ignore
+/// pub fn helper() {}
+/// ```
+pub fn with_ignored_doctest() {}
+"#;
+    let nodes = search(source, &Pattern::parse("kind:fn").unwrap()).unwrap();
+    let node = nodes.iter().find(|n| n.ident.as_deref() == Some("with_ignored_doctest")).unwrap();
+
+    // `search_doctests` and the `doctest(...)` pattern clause must agree:
+    // rustdoc never compiles an `ignore`d fence, so neither should find a
+    // `kind:fn` match inside it.
+    assert!(search_doctests(node, &Pattern::parse("kind:fn").unwrap()).is_empty());
+
+    let clause_pattern = Pattern::parse("doctest(kind:fn)").unwrap();
+    assert!(!clause_pattern.matches(node));
+}
+
+#[test]
+fn doctest_pattern_clause_recurses_into_nested_ast() {
+    let source = r#"
+/// ```
+/// pub fn helper() {}
+/// ```
+pub fn with_nested_fn() {}
+
+/// ```
+/// let x = 1;
+/// ```
+pub fn without_nested_fn() {}
+"#;
+    let pattern = Pattern::parse(r#"doctest(kind:fn pub:true)"#).unwrap();
+    let matches = search(source, &pattern).unwrap();
+    let idents: Vec<_> = matches.iter().filter_map(|n| n.ident.clone()).collect();
+    assert_eq!(idents, vec!["with_nested_fn".to_string()]);
+}
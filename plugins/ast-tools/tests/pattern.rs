@@ -0,0 +1,117 @@
+use ast_tools::pattern::Pattern;
+use ast_tools::search;
+
+const FIXTURE: &str = include_str!("fixtures/multi-lang/sample.rs");
+
+#[test]
+fn doc_text_is_attached_to_its_item() {
+    let pattern = Pattern::parse(r#"kind:struct doc:"greets people""#).unwrap();
+    let matches = search(FIXTURE, &pattern).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].ident.as_deref(), Some("Greeter"));
+}
+
+#[test]
+fn doc_regex_matches_across_items() {
+    let pattern = Pattern::parse(r"doc:/^Say|^A\b/").unwrap();
+    let matches = search(FIXTURE, &pattern).unwrap();
+    let mut idents: Vec<_> = matches.iter().filter_map(|n| n.ident.clone()).collect();
+    idents.sort();
+    assert_eq!(
+        idents,
+        vec!["Greeter".to_string(), "Person".to_string(), "farewell".to_string()]
+    );
+}
+
+#[test]
+fn no_undocumented_public_items_in_fixture() {
+    let pattern = Pattern::parse("pub:true no_doc").unwrap();
+    let matches = search(FIXTURE, &pattern).unwrap();
+    assert!(matches.is_empty(), "fixture's public API should be fully documented");
+}
+
+#[test]
+fn finds_method_inside_impl_block() {
+    let pattern = Pattern::parse(r#"kind:fn doc:"Greet someone""#).unwrap();
+    let matches = search(FIXTURE, &pattern).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].ident.as_deref(), Some("greet"));
+}
+
+#[test]
+fn has_section_finds_examples_heading() {
+    let source = r#"
+        /// Adds two numbers.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// assert_eq!(add(1, 2), 3);
+        /// ```
+        pub fn add(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        /// Subtracts two numbers.
+        pub fn sub(a: i32, b: i32) -> i32 {
+            a - b
+        }
+    "#;
+    let pattern = Pattern::parse(r#"has_section("Examples")"#).unwrap();
+    let matches = search(source, &pattern).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].ident.as_deref(), Some("add"));
+
+    // Scoped to `kind:fn`: the source has no crate-level `//!` doc, so an
+    // unscoped `no_doc` now also (correctly) matches the synthesized
+    // crate-root node.
+    let pattern = Pattern::parse("kind:fn no_doc").unwrap();
+    assert!(search(source, &pattern).unwrap().is_empty());
+}
+
+#[test]
+fn has_section_ignores_hash_lines_inside_fenced_code() {
+    let source = r#"
+        /// Builds a map.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// # use std::collections::HashMap;
+        /// let m: HashMap<String, i32> = HashMap::new();
+        /// ```
+        ///
+        /// ```
+        /// #[derive(Debug)]
+        /// struct Unit;
+        /// ```
+        pub fn build() -> i32 {
+            0
+        }
+    "#;
+    let pattern = Pattern::parse("kind:fn").unwrap();
+    let nodes = search(source, &pattern).unwrap();
+    assert_eq!(nodes[0].doc.sections(), vec!["Examples".to_string()]);
+}
+
+#[test]
+fn crate_level_inner_doc_is_queryable() {
+    let source = "//! A tiny crate.\n//! It does one thing.\n\npub fn bar() {}\n";
+    let pattern = Pattern::parse("kind:crate has_doc").unwrap();
+    let matches = search(source, &pattern).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].ident, None);
+    assert!(matches[0].doc.text.contains("tiny crate"));
+
+    // An unscoped `has_doc` also sees the crate root, so a source with no
+    // `//!` at all should have one `kind:crate no_doc` match.
+    let undocumented = "pub fn bar() {}\n";
+    let matches = search(undocumented, &Pattern::parse("kind:crate no_doc").unwrap()).unwrap();
+    assert_eq!(matches.len(), 1);
+}
+
+#[test]
+fn rejects_malformed_pattern() {
+    assert!(Pattern::parse("kind:bogus").is_err());
+    assert!(Pattern::parse(r#"doc:"unterminated"#).is_err());
+}
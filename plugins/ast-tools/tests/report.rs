@@ -0,0 +1,64 @@
+use ast_tools::pattern::Pattern;
+use ast_tools::report::{render_html, write_jsonl, ReportMatch};
+use ast_tools::search;
+
+const FIXTURE: &str = include_str!("fixtures/multi-lang/sample.rs");
+const FIXTURE_PATH: &str = "tests/fixtures/multi-lang/sample.rs";
+
+#[test]
+fn jsonl_streams_one_object_per_match_with_stable_fields() {
+    let pattern = Pattern::parse("pub:true").unwrap();
+    let matches: Vec<ReportMatch> = search(FIXTURE, &pattern)
+        .unwrap()
+        .iter()
+        .map(|node| ReportMatch::from_node(FIXTURE_PATH, FIXTURE, node))
+        .collect();
+    assert!(matches.len() >= 4, "Greeter, Person, greet and farewell should all match");
+
+    let mut buf = Vec::new();
+    write_jsonl(&matches, &mut buf).unwrap();
+    let text = String::from_utf8(buf).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), matches.len(), "exactly one JSON object per match");
+
+    let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(first["file"], FIXTURE_PATH);
+    assert!(first["span"]["start_line"].is_u64());
+    assert!(first["kind"].is_string());
+    assert!(!first["doc"].as_str().unwrap().is_empty());
+}
+
+#[test]
+fn html_report_links_matched_items_by_name() {
+    let pattern = Pattern::parse("pub:true").unwrap();
+    let matches: Vec<ReportMatch> = search(FIXTURE, &pattern)
+        .unwrap()
+        .iter()
+        .map(|node| ReportMatch::from_node(FIXTURE_PATH, FIXTURE, node))
+        .collect();
+
+    let html = render_html(&matches);
+    for name in ["Greeter", "Person", "greet", "farewell"] {
+        assert!(html.contains(&format!("<code>{name}</code>")), "missing entry for {name}");
+    }
+    assert!(html.contains("<nav>"));
+}
+
+#[test]
+fn html_report_highlights_captured_metavariables() {
+    use std::collections::HashMap;
+
+    let pattern = Pattern::parse("kind:fn").unwrap();
+    let node = search(FIXTURE, &pattern)
+        .unwrap()
+        .into_iter()
+        .find(|node| node.ident.as_deref() == Some("greet"))
+        .unwrap();
+
+    let mut captures = HashMap::new();
+    captures.insert("NAME".to_string(), "greet".to_string());
+    let found = ReportMatch::from_node_with_captures(FIXTURE_PATH, FIXTURE, &node, captures);
+
+    let html = render_html(std::slice::from_ref(&found));
+    assert!(html.contains("<mark class=\"meta\" title=\"NAME\">greet</mark>"));
+}
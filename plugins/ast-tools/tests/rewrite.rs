@@ -0,0 +1,91 @@
+use ast_tools::rewrite::{diff, Rewrite};
+
+#[test]
+fn renames_a_function_preserving_body_formatting() {
+    let rewrite = Rewrite::new(
+        "fn legacy_greet($ARGS) -> $RET { $BODY }",
+        "fn greet($ARGS) -> $RET { $BODY }",
+    )
+    .unwrap();
+    let source = "fn legacy_greet(name: &str) -> String {\n    let n = name;\n    // keep this comment\n    n.into()\n}\n";
+    let (rewritten, edits) = rewrite.apply(source).unwrap();
+    assert_eq!(edits.len(), 1);
+    assert!(rewritten.contains("fn greet(name: &str) -> String"));
+    assert!(rewritten.contains("// keep this comment"), "unrelated formatting/comments must survive");
+}
+
+#[test]
+fn rewrites_multiple_non_overlapping_matches_in_one_file() {
+    let rewrite = Rewrite::new("fn $NAME() -> bool { true }", "fn $NAME() -> bool { false }").unwrap();
+    let source = "fn a() -> bool { true }\n\nfn b() -> bool { true }\n";
+    let (rewritten, edits) = rewrite.apply(source).unwrap();
+    assert_eq!(edits.len(), 2);
+    assert_eq!(rewritten.matches("-> bool { false }").count(), 2);
+}
+
+#[test]
+fn dry_run_diff_shows_old_and_new_text() {
+    let rewrite = Rewrite::new("fn legacy_greet() {}", "fn greet() {}").unwrap();
+    let source = "fn legacy_greet() {}\n";
+    let matches = rewrite.find_matches(source).unwrap();
+    assert_eq!(matches.len(), 1);
+    let replacement = rewrite.render(&matches[0]);
+    let edit = ast_tools::rewrite::Edit { span: matches[0].span, replacement };
+    let text = diff(source, &edit);
+    assert!(text.contains("- fn legacy_greet"));
+    assert!(text.contains("+ fn greet"));
+}
+
+#[test]
+fn refuses_non_idempotent_rewrite() {
+    // The body wildcard means the rewritten function still matches the
+    // same shape, so applying it again would find a further "match" -
+    // the guard must refuse rather than let a rewrite loop silently.
+    let rewrite = Rewrite::new("fn $NAME($ARGS) { $BODY }", "fn $NAME($ARGS) { /* reviewed */ $BODY }").unwrap();
+    let source = "fn f() { g(); }\n";
+    let result = rewrite.apply(source);
+    assert!(result.is_err());
+}
+
+#[test]
+fn captures_metavariables_as_verbatim_source_text() {
+    let rewrite = Rewrite::new("fn $NAME($ARGS) -> $RET { $BODY }", "fn $NAME($ARGS) -> $RET { $BODY }").unwrap();
+    let source = "fn add(a: i32, b: i32) -> i32 { a + b }\n";
+    let matches = rewrite.find_matches(source).unwrap();
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].captures["NAME"], "add");
+    assert_eq!(matches[0].captures["ARGS"], "a: i32, b: i32");
+    assert_eq!(matches[0].captures["RET"], "i32");
+    assert_eq!(matches[0].captures["BODY"], "a + b");
+}
+
+#[test]
+fn rewrite_preserves_a_leading_doc_comment() {
+    let rewrite = Rewrite::new(
+        "fn legacy_greet($ARGS) -> $RET { $BODY }",
+        "fn greet($ARGS) -> $RET { $BODY }",
+    )
+    .unwrap();
+    let source = "/// Greets someone.\nfn legacy_greet(name: &str) -> String { name.into() }\n";
+    let (rewritten, edits) = rewrite.apply(source).unwrap();
+    assert_eq!(edits.len(), 1);
+    assert!(rewritten.contains("/// Greets someone."), "doc comment must survive the rewrite");
+    assert!(rewritten.contains("fn greet(name: &str) -> String"));
+}
+
+#[test]
+fn rewrite_preserves_a_leading_outer_attribute() {
+    let rewrite = Rewrite::new("struct Foo { $FIELDS }", "struct Bar { $FIELDS }").unwrap();
+    let source = "#[derive(Debug, Clone)]\nstruct Foo { x: i32 }\n";
+    let (rewritten, edits) = rewrite.apply(source).unwrap();
+    assert_eq!(edits.len(), 1);
+    assert!(rewritten.contains("#[derive(Debug, Clone)]"), "derive attribute must survive the rewrite");
+    assert!(rewritten.contains("struct Bar"));
+}
+
+#[test]
+fn non_matching_pattern_finds_nothing() {
+    let rewrite = Rewrite::new("struct $NAME { $FIELDS }", "struct $NAME { $FIELDS }").unwrap();
+    let source = "fn not_a_struct() {}\n";
+    assert!(rewrite.find_matches(source).unwrap().is_empty());
+}